@@ -1,6 +1,11 @@
 use hdk::prelude::*;
+use acp_integrity::evm::{self, EthReceipt};
 use acp_integrity::{AcpAgent, AcpJob, EntryTypes, LinkTypes};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+
+mod provider;
+mod signer;
+use provider::{default_stack, BaseProvider};
 
 /// Query input for browsing agents
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -36,6 +41,62 @@ pub struct WalletBalance {
     pub balance_eth: String,
 }
 
+/// Input for verifying a job's escrow deposit against an on-chain receipt.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct VerifyEscrowInput {
+    /// The job whose escrow deposit is being verified.
+    pub job_hash: ActionHash,
+    /// Raw `eth_getTransactionReceipt` JSON, fetched by the host bridge.
+    pub receipt_json: String,
+    /// Escrow/router contract the deposit must be paid to (`0x...`).
+    pub expected_to: String,
+}
+
+/// Result of a successful escrow verification.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct VerifyEscrowResult {
+    /// Decoded on-chain amount in wei.
+    pub verified_amount_wei: String,
+    /// Action hash of the updated job entry carrying the verified amount.
+    pub updated_job_hash: ActionHash,
+}
+
+/// Input for advancing a job to its next lifecycle phase.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AdvanceJobPhaseInput {
+    /// Action hash of the current job tip (original or latest update).
+    pub job_hash: ActionHash,
+    /// The phase to transition into.
+    pub new_phase: String,
+}
+
+/// A single phase change in a job's lifecycle, as walked from the update chain.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PhaseChange {
+    /// The phase recorded at this point in the chain.
+    pub phase: String,
+    /// Agent that authored the transition.
+    pub actor: AgentPubKey,
+    /// When the transition was committed.
+    pub timestamp: Timestamp,
+    /// Action hash of this create/update.
+    pub action_hash: ActionHash,
+}
+
+/// Signals emitted to subscribed clients on job lifecycle changes.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "type")]
+pub enum Signal {
+    /// A job successfully advanced to a new phase.
+    JobPhaseAdvanced {
+        job_hash: ActionHash,
+        previous_phase: String,
+        new_phase: String,
+        actor: AgentPubKey,
+        timestamp: Timestamp,
+    },
+}
+
 // ============================================================================
 // ANCHOR PATHS
 // ============================================================================
@@ -154,6 +215,7 @@ pub fn execute_acp_job(input: JobCreationInput) -> ExternResult<ActionHash> {
         created_at: now,
         current_phase: "requested".to_string(),
         deliverable: None,
+        verified_amount_wei: None,
     };
 
     // Create the job entry (will be validated by integrity zome)
@@ -180,16 +242,71 @@ pub fn execute_acp_job(input: JobCreationInput) -> ExternResult<ActionHash> {
     Ok(job_hash)
 }
 
+/// Verify a job's escrow deposit against its on-chain transaction receipt.
+///
+/// The host bridge fetches `eth_getTransactionReceipt` for the job's
+/// `escrow_hash` and passes the raw JSON in; the zome decodes it deterministically
+/// so every validator reaches the same conclusion. Verification requires the
+/// receipt's `transactionHash` to match the job's own `escrow_hash` - otherwise
+/// any successful, unrelated `Transfer` could be used to mark the job paid -
+/// and the receipt must be successful and contain an ERC-20 `Transfer` to
+/// `expected_to` of at least the amount declared in the job's
+/// `service_requirements` (`required_amount_wei`). On success the decoded amount
+/// is persisted onto the job via a CRUD update, allowing it to advance past
+/// `requested`.
+#[hdk_extern]
+pub fn verify_escrow(input: VerifyEscrowInput) -> ExternResult<VerifyEscrowResult> {
+    // Load the job being verified.
+    let record = get(input.job_hash.clone(), GetOptions::default())?.ok_or_else(|| {
+        wasm_error!(WasmErrorInner::Guest("job not found".to_string()))
+    })?;
+    let job: AcpJob = record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+        .ok_or_else(|| wasm_error!(WasmErrorInner::Guest("record is not an AcpJob".to_string())))?;
+
+    // The required deposit amount is declared in the job's service requirements.
+    let requirements: HashMap<String, String> = serde_json::from_str(&job.service_requirements)
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?;
+    let required_wei: u128 = requirements
+        .get("required_amount_wei")
+        .ok_or_else(|| wasm_error!(WasmErrorInner::Guest(
+            "service_requirements is missing required_amount_wei".to_string()
+        )))?
+        .parse()
+        .map_err(|_| wasm_error!(WasmErrorInner::Guest(
+            "required_amount_wei is not a valid integer".to_string()
+        )))?;
+
+    // Decode the receipt and confirm the transfer.
+    let receipt: EthReceipt = serde_json::from_str(&input.receipt_json)
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?;
+    let verified_amount_wei =
+        evm::verify_escrow_receipt(&receipt, &job.escrow_hash, &input.expected_to, required_wei)
+            .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?;
+
+    // Persist the verified amount onto the job.
+    let updated_job = AcpJob {
+        verified_amount_wei: Some(verified_amount_wei.clone()),
+        ..job
+    };
+    let updated_job_hash = update_entry(input.job_hash.clone(), &updated_job)?;
+
+    Ok(VerifyEscrowResult {
+        verified_amount_wei,
+        updated_job_hash,
+    })
+}
+
 /// Get wallet balance from Base L2
-/// 
-/// This function queries the EVM balance via an external RPC call.
-/// In production, this would use ethers.rs with an HTTP provider.
-/// 
-/// Note: Since HDK doesn't support direct HTTP calls, this needs to be
-/// handled through:
-/// 1. External service bridge (recommended)
-/// 2. Capability grants to external binary
-/// 3. Signal-based async pattern
+///
+/// Routes a single `eth_getBalance` call through the provider stack (base RPC
+/// layer + nonce manager + gas oracle, neither of which act on a read call).
+/// Request construction and the wei->eth decoding happen in-crate so the path
+/// is testable without a live node. The stack's transport is
+/// `HostRpcTransport`, which has no host bridge wired and returns an error for
+/// every call, so this function currently always returns that error.
 #[hdk_extern]
 pub fn get_wallet_balance(address: String) -> ExternResult<WalletBalance> {
     // Validate address format
@@ -199,20 +316,7 @@ pub fn get_wallet_balance(address: String) -> ExternResult<WalletBalance> {
         )));
     }
 
-    // NOTE: Actual balance query needs to be done via external call
-    // This is a placeholder that would be replaced with:
-    // - Call to external service via capability grant
-    // - Bridge to Node.js service that runs ethers.js
-    // - WebSocket message to OpenClaw runtime
-    //
-    // For now, return a placeholder response
-    // In production, integrate with Base RPC using external service
-
-    Ok(WalletBalance {
-        address: address.clone(),
-        balance_wei: "0".to_string(), // Placeholder - integrate with Base RPC
-        balance_eth: "0.0".to_string(),
-    })
+    default_stack().get_balance(&address)
 }
 
 /// Get all jobs for the current agent
@@ -238,6 +342,93 @@ pub fn get_my_jobs(_: ()) -> ExternResult<Vec<AcpJob>> {
     Ok(jobs)
 }
 
+/// Advance a job to its next lifecycle phase.
+///
+/// Creates a CRUD update on the job recording the new phase, with the actor and
+/// timestamp carried by the update action itself. Illegal transitions (skipping
+/// phases, or moving out of a terminal phase) are rejected by the integrity
+/// `validate` callback, which also requires `requested -> negotiation` to carry
+/// a `verified_amount_wei` from a prior [`verify_escrow`] call. On success a
+/// [`Signal::JobPhaseAdvanced`] is emitted so subscribed clients receive live
+/// lifecycle updates.
+#[hdk_extern]
+pub fn advance_job_phase(input: AdvanceJobPhaseInput) -> ExternResult<ActionHash> {
+    let record = get(input.job_hash.clone(), GetOptions::default())?.ok_or_else(|| {
+        wasm_error!(WasmErrorInner::Guest("job not found".to_string()))
+    })?;
+    let job: AcpJob = record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+        .ok_or_else(|| wasm_error!(WasmErrorInner::Guest("record is not an AcpJob".to_string())))?;
+
+    let previous_phase = job.current_phase.clone();
+    let updated_job = AcpJob {
+        current_phase: input.new_phase.clone(),
+        ..job
+    };
+    let updated_hash = update_entry(input.job_hash.clone(), &updated_job)?;
+
+    emit_signal(Signal::JobPhaseAdvanced {
+        job_hash: updated_hash.clone(),
+        previous_phase,
+        new_phase: input.new_phase,
+        actor: agent_info()?.agent_initial_pubkey,
+        timestamp: sys_time()?,
+    })?;
+
+    Ok(updated_hash)
+}
+
+/// Walk a job's update tree and return every phase change with its metadata.
+///
+/// `details.updates` ordering is not guaranteed and authors can create
+/// concurrent updates on the same tip, so this follows every branch rather
+/// than picking a single one, then sorts the combined history by timestamp
+/// (ties broken by action hash) so every authority reaches the same result.
+#[hdk_extern]
+pub fn get_job_history(job_hash: ActionHash) -> ExternResult<Vec<PhaseChange>> {
+    let mut history = Vec::new();
+    let mut visited = HashSet::new();
+    let mut frontier = vec![job_hash];
+
+    while let Some(current) = frontier.pop() {
+        if !visited.insert(current.clone()) {
+            continue;
+        }
+
+        let details = match get_details(current, GetOptions::default())? {
+            Some(Details::Record(details)) => details,
+            _ => continue,
+        };
+
+        if let Some(change) = phase_change_from_record(&details.record) {
+            history.push(change);
+        }
+
+        frontier.extend(details.updates.iter().map(|update| update.action_address().clone()));
+    }
+
+    history.sort_by(|a, b| {
+        a.timestamp
+            .cmp(&b.timestamp)
+            .then_with(|| a.action_hash.cmp(&b.action_hash))
+    });
+
+    Ok(history)
+}
+
+/// Extract the phase and action metadata from a job record.
+fn phase_change_from_record(record: &Record) -> Option<PhaseChange> {
+    let job: AcpJob = record.entry().to_app_option().ok()??;
+    Some(PhaseChange {
+        phase: job.current_phase,
+        actor: record.action().author().clone(),
+        timestamp: record.action().timestamp(),
+        action_hash: record.action_address().clone(),
+    })
+}
+
 /// Get a specific job by its ActionHash
 #[hdk_extern]
 pub fn get_job(job_hash: ActionHash) -> ExternResult<Option<AcpJob>> {
@@ -0,0 +1,384 @@
+//! Stackable EVM provider middleware.
+//!
+//! Modeled on the ethers-rs middleware architecture: a [`BaseProvider`] exposes
+//! a single `request` entry point, and each middleware layer wraps an inner
+//! provider, handling the calls it cares about and forwarding everything else
+//! down the stack. The bottom layer ([`RpcProvider`]) hands each serializable
+//! [`JsonRpcRequest`] unchanged to an [`RpcTransport`]. The only transport in
+//! this crate, [`HostRpcTransport`], has no host bridge wired and returns an
+//! error for every call. Layering and
+//! result decoding stay in the crate so the stack is testable without a live
+//! node - see the `tests` module, which drives the full stack through a
+//! canned [`RpcTransport`].
+//!
+//! [`NonceManager`] and [`GasOracle`] only act on `eth_sendTransaction`, which
+//! no caller in this zome issues yet; they're wired in ahead of that
+//! transaction-signing path rather than bolted on once it exists, so
+//! `default_stack()` is already the shape every future send call will ride on.
+
+use acp_integrity::evm::EthReceipt;
+use core::cell::Cell;
+use hdk::prelude::*;
+use serde_json::json;
+
+use crate::WalletBalance;
+
+/// A JSON-RPC request crossing the capability-grant boundary.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct JsonRpcRequest {
+    /// JSON-RPC method, e.g. `eth_getBalance`.
+    pub method: String,
+    /// Positional parameters.
+    pub params: Vec<serde_json::Value>,
+    /// Request id.
+    pub id: u64,
+}
+
+/// A JSON-RPC response returned by the host bridge.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct JsonRpcResponse {
+    /// The `result` field, present on success.
+    pub result: Option<serde_json::Value>,
+    /// The `error` message, present on failure.
+    pub error: Option<String>,
+}
+
+impl JsonRpcResponse {
+    /// Extract the result, mapping a JSON-RPC error into a guest error.
+    pub fn into_result(self) -> ExternResult<serde_json::Value> {
+        if let Some(error) = self.error {
+            return Err(wasm_error!(WasmErrorInner::Guest(format!(
+                "JSON-RPC error: {}",
+                error
+            ))));
+        }
+        self.result
+            .ok_or_else(|| wasm_error!(WasmErrorInner::Guest("empty JSON-RPC result".to_string())))
+    }
+}
+
+/// Transport that actually executes a JSON-RPC call.
+///
+/// [`HostRpcTransport`] is the crate's host-side implementation (currently
+/// returning an error for every call); tests can supply a canned responder.
+pub trait RpcTransport {
+    fn send(&self, request: &JsonRpcRequest) -> ExternResult<JsonRpcResponse>;
+}
+
+/// A provider exposes a single request entry point and convenience decoders.
+pub trait BaseProvider {
+    /// Dispatch a JSON-RPC request through the stack.
+    fn request(&self, request: JsonRpcRequest) -> ExternResult<JsonRpcResponse>;
+
+    /// Fetch and decode `eth_getBalance` for `address`.
+    fn get_balance(&self, address: &str) -> ExternResult<WalletBalance> {
+        let response = self.request(JsonRpcRequest {
+            method: "eth_getBalance".to_string(),
+            params: vec![json!(address), json!("latest")],
+            id: 1,
+        })?;
+        let hex = response
+            .into_result()?
+            .as_str()
+            .ok_or_else(|| {
+                wasm_error!(WasmErrorInner::Guest("balance result is not a string".to_string()))
+            })?
+            .to_string();
+        let wei = parse_hex_u128(&hex)?;
+        Ok(WalletBalance {
+            address: address.to_string(),
+            balance_wei: wei.to_string(),
+            balance_eth: format_wei_to_eth(wei),
+        })
+    }
+
+    /// Fetch and decode `eth_getTransactionReceipt` for `tx_hash`.
+    fn get_transaction_receipt(&self, tx_hash: &str) -> ExternResult<EthReceipt> {
+        let response = self.request(JsonRpcRequest {
+            method: "eth_getTransactionReceipt".to_string(),
+            params: vec![json!(tx_hash)],
+            id: 1,
+        })?;
+        serde_json::from_value(response.into_result()?)
+            .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Base RPC layer
+// ----------------------------------------------------------------------------
+
+/// Bottom layer: forwards every request to the transport unchanged.
+pub struct RpcProvider<T: RpcTransport> {
+    transport: T,
+}
+
+impl<T: RpcTransport> RpcProvider<T> {
+    pub fn new(transport: T) -> Self {
+        Self { transport }
+    }
+}
+
+impl<T: RpcTransport> BaseProvider for RpcProvider<T> {
+    fn request(&self, request: JsonRpcRequest) -> ExternResult<JsonRpcResponse> {
+        self.transport.send(&request)
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Nonce-manager layer
+// ----------------------------------------------------------------------------
+
+/// Tracks the agent's transaction count and fills the `nonce` field on
+/// outgoing transactions, forwarding every other call to the inner provider.
+pub struct NonceManager<P: BaseProvider> {
+    inner: P,
+    nonce: Cell<Option<u64>>,
+}
+
+impl<P: BaseProvider> NonceManager<P> {
+    pub fn new(inner: P) -> Self {
+        Self {
+            inner,
+            nonce: Cell::new(None),
+        }
+    }
+
+    /// Seed the manager with a known starting nonce (e.g. from
+    /// `eth_getTransactionCount`).
+    pub fn with_nonce(inner: P, nonce: u64) -> Self {
+        Self {
+            inner,
+            nonce: Cell::new(Some(nonce)),
+        }
+    }
+
+    fn next_nonce(&self) -> u64 {
+        let current = self.nonce.get().unwrap_or(0);
+        self.nonce.set(Some(current + 1));
+        current
+    }
+}
+
+impl<P: BaseProvider> BaseProvider for NonceManager<P> {
+    fn request(&self, mut request: JsonRpcRequest) -> ExternResult<JsonRpcResponse> {
+        if request.method == "eth_sendTransaction" {
+            if let Some(serde_json::Value::Object(tx)) = request.params.get_mut(0) {
+                tx.entry("nonce".to_string())
+                    .or_insert_with(|| json!(format!("0x{:x}", self.next_nonce())));
+            }
+        }
+        self.inner.request(request)
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Gas-oracle layer
+// ----------------------------------------------------------------------------
+
+/// Fills fee fields (`maxFeePerGas`, `maxPriorityFeePerGas`) on outgoing
+/// transactions, forwarding every other call to the inner provider.
+pub struct GasOracle<P: BaseProvider> {
+    inner: P,
+    max_fee_per_gas: String,
+    max_priority_fee_per_gas: String,
+}
+
+impl<P: BaseProvider> GasOracle<P> {
+    pub fn new(inner: P, max_fee_per_gas: String, max_priority_fee_per_gas: String) -> Self {
+        Self {
+            inner,
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+        }
+    }
+}
+
+impl<P: BaseProvider> BaseProvider for GasOracle<P> {
+    fn request(&self, mut request: JsonRpcRequest) -> ExternResult<JsonRpcResponse> {
+        if request.method == "eth_sendTransaction" {
+            if let Some(serde_json::Value::Object(tx)) = request.params.get_mut(0) {
+                tx.entry("maxFeePerGas".to_string())
+                    .or_insert_with(|| json!(self.max_fee_per_gas));
+                tx.entry("maxPriorityFeePerGas".to_string())
+                    .or_insert_with(|| json!(self.max_priority_fee_per_gas));
+            }
+        }
+        self.inner.request(request)
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Host transport + default stack
+// ----------------------------------------------------------------------------
+
+/// Transport that bridges JSON-RPC to the host over a capability grant.
+///
+/// HDK cannot open sockets, so the host process that launched the conductor
+/// is meant to execute the call and return the response. That bridge isn't
+/// wired up yet, so every call fails loudly rather than fabricating a result
+/// - a missing bridge should not silently resolve `get_wallet_balance` to a
+/// balance of zero.
+pub struct HostRpcTransport;
+
+impl RpcTransport for HostRpcTransport {
+    fn send(&self, request: &JsonRpcRequest) -> ExternResult<JsonRpcResponse> {
+        Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "no host RPC bridge is wired for {}",
+            request.method
+        ))))
+    }
+}
+
+/// The canonical provider stack: base RPC wrapped by the nonce manager and gas
+/// oracle, as used by balance queries. `verify_escrow` does not go through it:
+/// it decodes receipt JSON passed in by the caller.
+pub fn default_stack() -> GasOracle<NonceManager<RpcProvider<HostRpcTransport>>> {
+    GasOracle::new(
+        NonceManager::new(RpcProvider::new(HostRpcTransport)),
+        "0x0".to_string(),
+        "0x0".to_string(),
+    )
+}
+
+// ----------------------------------------------------------------------------
+// Wei formatting
+// ----------------------------------------------------------------------------
+
+const WEI_PER_ETH: u128 = 1_000_000_000_000_000_000;
+
+fn parse_hex_u128(hex: &str) -> ExternResult<u128> {
+    u128::from_str_radix(hex.trim_start_matches("0x"), 16)
+        .map_err(|_| wasm_error!(WasmErrorInner::Guest(format!("invalid hex quantity: {}", hex))))
+}
+
+/// Format a wei amount as a decimal eth string, trimming trailing zeros.
+fn format_wei_to_eth(wei: u128) -> String {
+    let whole = wei / WEI_PER_ETH;
+    let frac = wei % WEI_PER_ETH;
+    if frac == 0 {
+        return whole.to_string();
+    }
+    let frac_str = format!("{:018}", frac);
+    let trimmed = frac_str.trim_end_matches('0');
+    format!("{}.{}", whole, trimmed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::collections::VecDeque;
+    use std::rc::Rc;
+
+    /// Transport stub that returns queued responses and records every
+    /// request it was handed, so middleware layers can be asserted on
+    /// without a live node.
+    struct CannedTransport {
+        responses: RefCell<VecDeque<JsonRpcResponse>>,
+        requests: Rc<RefCell<Vec<JsonRpcRequest>>>,
+    }
+
+    impl CannedTransport {
+        fn new(responses: Vec<JsonRpcResponse>, requests: Rc<RefCell<Vec<JsonRpcRequest>>>) -> Self {
+            Self {
+                responses: RefCell::new(responses.into()),
+                requests,
+            }
+        }
+    }
+
+    impl RpcTransport for CannedTransport {
+        fn send(&self, request: &JsonRpcRequest) -> ExternResult<JsonRpcResponse> {
+            self.requests.borrow_mut().push(request.clone());
+            self.responses
+                .borrow_mut()
+                .pop_front()
+                .ok_or_else(|| wasm_error!(WasmErrorInner::Guest("no canned response left".to_string())))
+        }
+    }
+
+    fn ok(result: serde_json::Value) -> JsonRpcResponse {
+        JsonRpcResponse {
+            result: Some(result),
+            error: None,
+        }
+    }
+
+    #[test]
+    fn full_stack_decodes_get_balance() {
+        let requests = Rc::new(RefCell::new(Vec::new()));
+        let transport = CannedTransport::new(vec![ok(json!("0x2540be400"))], requests);
+        let stack = GasOracle::new(
+            NonceManager::new(RpcProvider::new(transport)),
+            "0x3b9aca00".to_string(),
+            "0x3b9aca00".to_string(),
+        );
+
+        let balance = stack.get_balance("0xabc").unwrap();
+        assert_eq!(balance.balance_wei, "10000000000");
+    }
+
+    #[test]
+    fn nonce_manager_fills_and_increments_nonce_on_send() {
+        let requests = Rc::new(RefCell::new(Vec::new()));
+        let transport = CannedTransport::new(
+            vec![ok(json!("0x1")), ok(json!("0x1"))],
+            requests.clone(),
+        );
+        let manager = NonceManager::with_nonce(RpcProvider::new(transport), 5);
+
+        manager.request(send_tx_request()).unwrap();
+        manager.request(send_tx_request()).unwrap();
+
+        let sent = requests.borrow();
+        assert_eq!(nonce_of(&sent[0]), "0x5");
+        assert_eq!(nonce_of(&sent[1]), "0x6");
+    }
+
+    #[test]
+    fn nonce_manager_leaves_non_send_calls_untouched() {
+        let requests = Rc::new(RefCell::new(Vec::new()));
+        let transport = CannedTransport::new(vec![ok(json!("0x0"))], requests.clone());
+        let manager = NonceManager::new(RpcProvider::new(transport));
+
+        manager.get_balance("0xabc").unwrap();
+
+        assert!(requests.borrow()[0].params.get(0).unwrap().get("nonce").is_none());
+    }
+
+    #[test]
+    fn gas_oracle_fills_fee_fields_on_send() {
+        let requests = Rc::new(RefCell::new(Vec::new()));
+        let transport = CannedTransport::new(vec![ok(json!("0x1"))], requests.clone());
+        let oracle = GasOracle::new(
+            RpcProvider::new(transport),
+            "0x3b9aca00".to_string(),
+            "0x1dcd6500".to_string(),
+        );
+
+        oracle.request(send_tx_request()).unwrap();
+
+        let sent = requests.borrow();
+        let tx = sent[0].params.get(0).unwrap().as_object().unwrap();
+        assert_eq!(tx.get("maxFeePerGas").unwrap(), "0x3b9aca00");
+        assert_eq!(tx.get("maxPriorityFeePerGas").unwrap(), "0x1dcd6500");
+    }
+
+    fn send_tx_request() -> JsonRpcRequest {
+        JsonRpcRequest {
+            method: "eth_sendTransaction".to_string(),
+            params: vec![json!({ "to": "0xabc", "value": "0x1" })],
+            id: 1,
+        }
+    }
+
+    fn nonce_of(request: &JsonRpcRequest) -> String {
+        request.params[0]
+            .get("nonce")
+            .unwrap()
+            .as_str()
+            .unwrap()
+            .to_string()
+    }
+}
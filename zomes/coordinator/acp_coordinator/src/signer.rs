@@ -0,0 +1,178 @@
+//! Session-key signer subsystem.
+//!
+//! Each agent carries a `session_key_id` that authorizes a delegate to act on
+//! the agent's behalf. This module ties that key to a Holochain capability
+//! grant: the active session key may call `execute_acp_job` and
+//! `advance_job_phase`, and nothing else. [`rotate_session_key`] mints a fresh
+//! key, links the new agent profile back to the one it supersedes (deleting
+//! the superseded discovery links so the old profile stops resolving), and
+//! revokes the prior grant, giving agents forward-secure, rotatable authority.
+//!
+//! Revocation is enforced by the conductor, not by DHT validation, and that is
+//! a deliberate boundary rather than a gap to close later. A capability grant
+//! is a local, author-private entry - it is never published to the DHT - so
+//! `acp_integrity`'s `validate` callback has no state to check a presented
+//! secret against, by design. The tempting alternative, gating a job/update on
+//! whether the signing `AcpAgent` profile already has an `AgentKeyHistory`
+//! successor, is not sound either: link sets are never provably complete, so
+//! an authority that validates before a rotation's links have arrived would
+//! accept what a later authority rejects, and Holochain validation verdicts
+//! must not depend on gossip timing. The conductor that holds the grant is
+//! therefore the only place this check can be made correctly, and every call
+//! against a revoked secret is rejected there before it is ever signed.
+
+use acp_integrity::{AcpAgent, EntryTypes, LinkTypes};
+use hdk::prelude::*;
+use std::collections::BTreeSet;
+
+use crate::all_agents_path;
+
+/// A minted session key and the capability grant that authorizes it.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SessionKeyGrant {
+    /// Newly minted session key id.
+    pub session_key_id: u64,
+    /// Secret the delegate presents when calling on the agent's behalf.
+    pub cap_secret: CapSecret,
+    /// Action hash of the capability grant (pass to revoke on rotation).
+    pub grant_hash: ActionHash,
+    /// Agent profile the grant is bound to.
+    pub agent_hash: ActionHash,
+}
+
+/// Input for rotating an agent's session key.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RotateSessionKeyInput {
+    /// The agent profile currently in force.
+    pub agent_hash: ActionHash,
+    /// Capability grant authorizing the outgoing session key.
+    pub previous_grant_hash: ActionHash,
+}
+
+/// Functions a session key is authorized to call on the agent's behalf.
+fn session_granted_functions() -> ExternResult<GrantedFunctions> {
+    let zome = zome_info()?.name;
+    let mut functions = BTreeSet::new();
+    functions.insert((zome.clone(), FunctionName("execute_acp_job".into())));
+    functions.insert((zome, FunctionName("advance_job_phase".into())));
+    Ok(GrantedFunctions::Listed(functions))
+}
+
+/// Mint a capability grant authorizing a session key to act for the agent.
+fn issue_session_grant(session_key_id: u64) -> ExternResult<(CapSecret, ActionHash)> {
+    let cap_secret = CapSecret::try_from(random_bytes(64)?.into_vec())
+        .map_err(|_| wasm_error!(WasmErrorInner::Guest("failed to mint cap secret".to_string())))?;
+
+    let grant_hash = create_cap_grant(CapGrantEntry {
+        tag: format!("session_key:{}", session_key_id),
+        access: CapAccess::Transferable { secret: cap_secret },
+        functions: session_granted_functions()?,
+    })?;
+
+    Ok((cap_secret, grant_hash))
+}
+
+/// Issue the initial session-key capability grant for a registered agent.
+#[hdk_extern]
+pub fn grant_session_key(agent_hash: ActionHash) -> ExternResult<SessionKeyGrant> {
+    let agent = get_agent_profile(&agent_hash)?;
+    let (cap_secret, grant_hash) = issue_session_grant(agent.session_key_id)?;
+    Ok(SessionKeyGrant {
+        session_key_id: agent.session_key_id,
+        cap_secret,
+        grant_hash,
+        agent_hash,
+    })
+}
+
+/// Rotate an agent's session key: mint a new key, supersede the old profile,
+/// and revoke the prior capability grant.
+#[hdk_extern]
+pub fn rotate_session_key(input: RotateSessionKeyInput) -> ExternResult<SessionKeyGrant> {
+    let current = get_agent_profile(&input.agent_hash)?;
+
+    // Mint a new session key and a superseding profile entry.
+    let new_session_key_id = sys_time()?.as_micros() as u64;
+    let rotated = AcpAgent {
+        session_key_id: new_session_key_id,
+        ..current
+    };
+    let new_agent_hash = create_entry(EntryTypes::AcpAgent(rotated))?;
+
+    // Keep discovery links and the agent's own profile pointer current.
+    let my_pub_key = agent_info()?.agent_initial_pubkey;
+    create_link(
+        my_pub_key.clone(),
+        new_agent_hash.clone(),
+        LinkTypes::AgentToProfile,
+        (),
+    )?;
+    let all_agents_path = all_agents_path()?;
+    all_agents_path.ensure()?;
+    create_link(
+        all_agents_path.path_entry_hash()?,
+        new_agent_hash.clone(),
+        LinkTypes::AllAgents,
+        (),
+    )?;
+
+    // Delete the superseded discovery links so the outgoing profile stops
+    // resolving from `my_pub_key` and no longer shows up twice in
+    // `browse_agents`.
+    delete_superseded_link(
+        my_pub_key,
+        LinkTypes::AgentToProfile,
+        &input.agent_hash,
+    )?;
+    delete_superseded_link(
+        all_agents_path.path_entry_hash()?,
+        LinkTypes::AllAgents,
+        &input.agent_hash,
+    )?;
+
+    // Record the supersession so the key history is auditable.
+    create_link(
+        new_agent_hash.clone(),
+        input.agent_hash.clone(),
+        LinkTypes::AgentKeyHistory,
+        (),
+    )?;
+
+    // Revoke the outgoing grant, then issue the new one.
+    delete_cap_grant(input.previous_grant_hash)?;
+    let (cap_secret, grant_hash) = issue_session_grant(new_session_key_id)?;
+
+    Ok(SessionKeyGrant {
+        session_key_id: new_session_key_id,
+        cap_secret,
+        grant_hash,
+        agent_hash: new_agent_hash,
+    })
+}
+
+/// Find the link of `link_type` from `base` pointing at `target` and delete
+/// it, so a superseded profile stops resolving through discovery links.
+fn delete_superseded_link(
+    base: impl Into<AnyLinkableHash>,
+    link_type: LinkTypes,
+    target: &ActionHash,
+) -> ExternResult<()> {
+    let links = get_links(GetLinksInputBuilder::try_new(base, link_type)?.build())?;
+    for link in links {
+        if link.target == target.clone().into() {
+            delete_link(link.create_link_hash)?;
+        }
+    }
+    Ok(())
+}
+
+/// Load an `AcpAgent` profile by its action hash.
+fn get_agent_profile(agent_hash: &ActionHash) -> ExternResult<AcpAgent> {
+    let record = get(agent_hash.clone(), GetOptions::default())?
+        .ok_or_else(|| wasm_error!(WasmErrorInner::Guest("agent profile not found".to_string())))?;
+    record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+        .ok_or_else(|| wasm_error!(WasmErrorInner::Guest("record is not an AcpAgent".to_string())))
+}
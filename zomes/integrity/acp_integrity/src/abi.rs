@@ -0,0 +1,388 @@
+//! ABI-driven typed bindings for the escrow/router contract.
+//!
+//! A small analogue of ethabi-derive: parse a contract ABI once and expose
+//! typed encoders/decoders. Function selectors are the first four bytes of
+//! `keccak256(signature)`; event decoders are keyed by the `keccak256` topic
+//! hash. Encoding/decoding is pure and in-WASM so results are deterministic
+//! across validators, and parsed ABIs are held in an LRU cache to avoid
+//! re-parsing on every call. Escrow verification and any future
+//! `inInstruction`-style deposit lookup go through [`Contract::decode_event`] /
+//! [`Contract::encode_call`] rather than ad-hoc `0x`-string checks.
+//!
+//! `uint256` is narrowed to `u128` (see [`Token::Uint256`]) - fine for escrow
+//! amounts, not a general-purpose decoder for e.g. max-`uint256` approvals.
+
+use core::cell::RefCell;
+use hdi::prelude::*;
+
+use crate::evm::{from_hex, keccak256, to_hex};
+
+/// The minimal ERC-20 ABI needed to decode escrow `Transfer` deposits.
+pub const ERC20_ABI: &str = r#"[
+    {
+        "type": "event",
+        "name": "Transfer",
+        "inputs": [
+            { "name": "from", "type": "address", "indexed": true },
+            { "name": "to", "type": "address", "indexed": true },
+            { "name": "value", "type": "uint256", "indexed": false }
+        ]
+    }
+]"#;
+
+/// A single ABI parameter.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AbiParam {
+    #[serde(default)]
+    pub name: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+    #[serde(default)]
+    pub indexed: bool,
+}
+
+/// A single ABI item (function or event).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AbiItem {
+    #[serde(rename = "type")]
+    pub item_type: String,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub inputs: Vec<AbiParam>,
+    #[serde(default)]
+    pub outputs: Vec<AbiParam>,
+}
+
+/// A decoded ABI value. Only the static types escrow logic needs are modeled.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Token {
+    /// `address`, rendered as lowercase `0x...`.
+    Address(String),
+    /// `uint256`, rendered as a decimal string.
+    ///
+    /// Narrowed to the `u128` range: values are encoded into the low 16
+    /// bytes of the word and decoding rejects anything using the high 16
+    /// bytes with [`AbiError::ValueOverflow`]. That covers escrow amounts
+    /// and ERC-20 transfer values fine, but this is not a general-purpose
+    /// `uint256` - max-`uint256` approvals and balances above `u128::MAX`
+    /// (2^128 - 1) do not round-trip.
+    Uint256(String),
+    /// `bytes32`, the raw 32-byte word.
+    Bytes32([u8; 32]),
+}
+
+/// Errors raised while parsing an ABI or coding arguments/events.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AbiError {
+    /// The ABI JSON could not be parsed.
+    Parse(String),
+    /// No function/event with the requested name exists.
+    NotFound(String),
+    /// The log's `topics[0]` did not match the event signature hash.
+    TopicMismatch,
+    /// An argument count or word layout was wrong.
+    BadLayout(String),
+    /// An unsupported ABI type was encountered.
+    UnsupportedType(String),
+    /// A hex field was malformed.
+    MalformedHex(String),
+    /// A `uint256` value used more than the low 16 bytes (i.e. exceeded
+    /// `u128::MAX`); see the note on [`Token::Uint256`].
+    ValueOverflow,
+}
+
+impl core::fmt::Display for AbiError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            AbiError::Parse(s) => write!(f, "failed to parse ABI: {}", s),
+            AbiError::NotFound(s) => write!(f, "ABI item not found: {}", s),
+            AbiError::TopicMismatch => write!(f, "log topic does not match event signature"),
+            AbiError::BadLayout(s) => write!(f, "unexpected ABI layout: {}", s),
+            AbiError::UnsupportedType(s) => write!(f, "unsupported ABI type: {}", s),
+            AbiError::MalformedHex(s) => write!(f, "malformed hex: {}", s),
+            AbiError::ValueOverflow => write!(f, "uint256 value exceeds supported range"),
+        }
+    }
+}
+
+/// A parsed contract ABI with typed encode/decode helpers.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Contract {
+    items: Vec<AbiItem>,
+}
+
+impl Contract {
+    /// Parse an ABI JSON document, returning a cached [`Contract`] when the
+    /// same document has been loaded before.
+    pub fn load(abi_json: &str) -> Result<Contract, AbiError> {
+        ABI_CACHE.with(|cache| cache.borrow_mut().get_or_parse(abi_json))
+    }
+
+    fn parse(abi_json: &str) -> Result<Contract, AbiError> {
+        let items: Vec<AbiItem> =
+            serde_json::from_str(abi_json).map_err(|e| AbiError::Parse(e.to_string()))?;
+        Ok(Contract { items })
+    }
+
+    fn item(&self, item_type: &str, name: &str) -> Result<&AbiItem, AbiError> {
+        self.items
+            .iter()
+            .find(|i| i.item_type == item_type && i.name == name)
+            .ok_or_else(|| AbiError::NotFound(format!("{} {}", item_type, name)))
+    }
+
+    /// The 4-byte selector for a function: `keccak256(signature)[..4]`.
+    pub fn function_selector(&self, name: &str) -> Result<[u8; 4], AbiError> {
+        let signature = canonical_signature(self.item("function", name)?);
+        let hash = keccak256(signature.as_bytes());
+        let mut selector = [0u8; 4];
+        selector.copy_from_slice(&hash[..4]);
+        Ok(selector)
+    }
+
+    /// The `keccak256` topic hash identifying an event.
+    pub fn event_topic0(&self, name: &str) -> Result<[u8; 32], AbiError> {
+        let signature = canonical_signature(self.item("event", name)?);
+        Ok(keccak256(signature.as_bytes()))
+    }
+
+    /// Encode a function call as `selector || head(arg0) || head(arg1) ...`.
+    ///
+    /// Only static types (`address`, `uint256`, `bytes32`) are supported, which
+    /// covers the escrow/router surface.
+    pub fn encode_call(&self, name: &str, args: &[Token]) -> Result<Vec<u8>, AbiError> {
+        let item = self.item("function", name)?;
+        if item.inputs.len() != args.len() {
+            return Err(AbiError::BadLayout(format!(
+                "{} expects {} args, got {}",
+                name,
+                item.inputs.len(),
+                args.len()
+            )));
+        }
+        let mut out = self.function_selector(name)?.to_vec();
+        for (param, token) in item.inputs.iter().zip(args) {
+            out.extend_from_slice(&encode_token(&param.kind, token)?);
+        }
+        Ok(out)
+    }
+
+    /// Decode an event log into its arguments, matching `topics[0]` against the
+    /// event signature hash. Indexed arguments come from the remaining topics,
+    /// non-indexed arguments from the packed `data` words.
+    pub fn decode_event(
+        &self,
+        name: &str,
+        topics: &[String],
+        data: &str,
+    ) -> Result<Vec<Token>, AbiError> {
+        let item = self.item("event", name)?;
+        let topic0 = to_hex(&self.event_topic0(name)?);
+        match topics.first() {
+            Some(t) if eq_ignore_hex(t, &topic0) => {}
+            _ => return Err(AbiError::TopicMismatch),
+        }
+
+        let data_words = split_words(data)?;
+        let mut tokens = Vec::with_capacity(item.inputs.len());
+        let mut topic_idx = 1;
+        let mut data_idx = 0;
+        for param in &item.inputs {
+            let word = if param.indexed {
+                let topic = topics
+                    .get(topic_idx)
+                    .ok_or_else(|| AbiError::BadLayout("missing indexed topic".to_string()))?;
+                topic_idx += 1;
+                word_from_hex(topic)?
+            } else {
+                let word = data_words
+                    .get(data_idx)
+                    .copied()
+                    .ok_or_else(|| AbiError::BadLayout("missing data word".to_string()))?;
+                data_idx += 1;
+                word
+            };
+            tokens.push(decode_token(&param.kind, &word)?);
+        }
+        Ok(tokens)
+    }
+}
+
+/// Canonical `name(type,type,...)` signature for selector/topic hashing.
+fn canonical_signature(item: &AbiItem) -> String {
+    let types: Vec<&str> = item.inputs.iter().map(|p| p.kind.as_str()).collect();
+    format!("{}({})", item.name, types.join(","))
+}
+
+fn eq_ignore_hex(a: &str, b: &str) -> bool {
+    a.trim_start_matches("0x")
+        .eq_ignore_ascii_case(b.trim_start_matches("0x"))
+}
+
+fn encode_token(kind: &str, token: &Token) -> Result<[u8; 32], AbiError> {
+    let mut word = [0u8; 32];
+    match (kind, token) {
+        ("address", Token::Address(addr)) => {
+            let bytes = from_hex(addr).map_err(|e| AbiError::MalformedHex(e.to_string()))?;
+            if bytes.len() != 20 {
+                return Err(AbiError::BadLayout("address must be 20 bytes".to_string()));
+            }
+            word[12..].copy_from_slice(&bytes);
+        }
+        ("uint256", Token::Uint256(dec)) => {
+            let value: u128 = dec.parse().map_err(|_| AbiError::ValueOverflow)?;
+            word[16..].copy_from_slice(&value.to_be_bytes());
+        }
+        ("bytes32", Token::Bytes32(bytes)) => {
+            word.copy_from_slice(bytes);
+        }
+        (kind, _) => return Err(AbiError::UnsupportedType(kind.to_string())),
+    }
+    Ok(word)
+}
+
+fn decode_token(kind: &str, word: &[u8; 32]) -> Result<Token, AbiError> {
+    match kind {
+        "address" => Ok(Token::Address(to_hex(&word[12..]))),
+        "uint256" => {
+            if word[..16].iter().any(|b| *b != 0) {
+                return Err(AbiError::ValueOverflow);
+            }
+            let mut buf = [0u8; 16];
+            buf.copy_from_slice(&word[16..]);
+            Ok(Token::Uint256(u128::from_be_bytes(buf).to_string()))
+        }
+        "bytes32" => Ok(Token::Bytes32(*word)),
+        other => Err(AbiError::UnsupportedType(other.to_string())),
+    }
+}
+
+fn word_from_hex(hex: &str) -> Result<[u8; 32], AbiError> {
+    let bytes = from_hex(hex).map_err(|e| AbiError::MalformedHex(e.to_string()))?;
+    if bytes.len() != 32 {
+        return Err(AbiError::BadLayout("word must be 32 bytes".to_string()));
+    }
+    let mut word = [0u8; 32];
+    word.copy_from_slice(&bytes);
+    Ok(word)
+}
+
+fn split_words(data: &str) -> Result<Vec<[u8; 32]>, AbiError> {
+    let bytes = from_hex(data).map_err(|e| AbiError::MalformedHex(e.to_string()))?;
+    if bytes.len() % 32 != 0 {
+        return Err(AbiError::BadLayout("data is not word-aligned".to_string()));
+    }
+    Ok(bytes.chunks_exact(32).map(|c| {
+        let mut word = [0u8; 32];
+        word.copy_from_slice(c);
+        word
+    }).collect())
+}
+
+// ----------------------------------------------------------------------------
+// LRU cache of parsed ABIs
+// ----------------------------------------------------------------------------
+
+thread_local! {
+    static ABI_CACHE: RefCell<AbiCache> = RefCell::new(AbiCache::new(8));
+}
+
+/// A tiny LRU keyed by the `keccak256` digest of the ABI document. The most
+/// recently used entry sits at the back; eviction drops from the front.
+struct AbiCache {
+    capacity: usize,
+    entries: Vec<([u8; 32], Contract)>,
+}
+
+impl AbiCache {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: Vec::new(),
+        }
+    }
+
+    fn get_or_parse(&mut self, abi_json: &str) -> Result<Contract, AbiError> {
+        let key = keccak256(abi_json.as_bytes());
+        if let Some(pos) = self.entries.iter().position(|(k, _)| *k == key) {
+            let (key, contract) = self.entries.remove(pos);
+            self.entries.push((key, contract.clone()));
+            return Ok(contract);
+        }
+
+        let contract = Contract::parse(abi_json)?;
+        if self.entries.len() >= self.capacity {
+            self.entries.remove(0);
+        }
+        self.entries.push((key, contract.clone()));
+        Ok(contract)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn address_word(addr: &str) -> [u8; 32] {
+        let bytes = from_hex(addr).unwrap();
+        let mut word = [0u8; 32];
+        word[12..].copy_from_slice(&bytes);
+        word
+    }
+
+    #[test]
+    fn decode_event_roundtrips_transfer() {
+        let contract = Contract::load(ERC20_ABI).unwrap();
+        let topic0 = to_hex(&contract.event_topic0("Transfer").unwrap());
+        let from = format!("0x{}", "11".repeat(20));
+        let to = format!("0x{}", "22".repeat(20));
+        let topics = vec![topic0, to_hex(&address_word(&from)), to_hex(&address_word(&to))];
+        let data = to_hex(&encode_token("uint256", &Token::Uint256("42".to_string())).unwrap());
+
+        let tokens = contract.decode_event("Transfer", &topics, &data).unwrap();
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Address(from),
+                Token::Address(to),
+                Token::Uint256("42".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn decode_event_rejects_mismatched_topic() {
+        let contract = Contract::load(ERC20_ABI).unwrap();
+        let bogus_topic0 = to_hex(&keccak256(b"NotTransfer(address)"));
+        let result = contract.decode_event("Transfer", &[bogus_topic0], "0x");
+        assert_eq!(result, Err(AbiError::TopicMismatch));
+    }
+
+    #[test]
+    fn uint256_round_trips_within_u128_range() {
+        let word = encode_token("uint256", &Token::Uint256(u128::MAX.to_string())).unwrap();
+        assert_eq!(
+            decode_token("uint256", &word).unwrap(),
+            Token::Uint256(u128::MAX.to_string())
+        );
+    }
+
+    #[test]
+    fn uint256_above_u128_max_overflows() {
+        // Bit set in the high 16 bytes: a value above what Token::Uint256
+        // (narrowed to u128, see its doc comment) can represent.
+        let mut word = [0u8; 32];
+        word[0] = 1;
+        assert_eq!(decode_token("uint256", &word), Err(AbiError::ValueOverflow));
+    }
+
+    #[test]
+    fn function_selector_is_first_four_bytes_of_keccak() {
+        let abi = r#"[{"type":"function","name":"deposit","inputs":[{"name":"amount","type":"uint256"}]}]"#;
+        let contract = Contract::load(abi).unwrap();
+        let selector = contract.function_selector("deposit").unwrap();
+        let expected = keccak256(b"deposit(uint256)");
+        assert_eq!(&selector[..], &expected[..4]);
+    }
+}
@@ -0,0 +1,398 @@
+//! Deterministic EVM log/receipt decoding used by escrow verification.
+//!
+//! HDK cannot perform HTTP itself, so the raw `eth_getTransactionReceipt`
+//! response is fetched by the host bridge and handed to the zome as JSON.
+//! The decoding and matching logic lives here so that escrow verification is
+//! deterministic and replayable across all validators: given the same receipt
+//! every agent derives the same decoded transfer.
+
+use hdi::prelude::*;
+
+/// A single log entry from an `eth_getTransactionReceipt` response.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct EthLog {
+    /// Contract that emitted the log (`0x...`, 42 chars).
+    pub address: String,
+    /// Indexed topics; `topics[0]` is the event signature hash.
+    pub topics: Vec<String>,
+    /// ABI-encoded non-indexed arguments (`0x` + 32-byte words).
+    pub data: String,
+}
+
+/// The subset of an `eth_getTransactionReceipt` response we rely on.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct EthReceipt {
+    /// Hash of the transaction this receipt belongs to. Checked against the
+    /// job's `escrow_hash` so a receipt for an unrelated (if successful)
+    /// transfer can't be substituted to verify a job it was never paid for.
+    #[serde(rename = "transactionHash")]
+    pub transaction_hash: String,
+    /// Transaction status, `0x1` on success.
+    pub status: String,
+    /// Emitted logs.
+    pub logs: Vec<EthLog>,
+}
+
+/// A decoded ERC-20 `Transfer(address,address,uint256)` event.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DecodedTransfer {
+    /// Indexed sender address (`0x...`, lowercase).
+    pub from: String,
+    /// Indexed recipient address (`0x...`, lowercase).
+    pub to: String,
+    /// Transferred amount in wei, decimal string.
+    pub value_wei: String,
+}
+
+/// Errors produced while decoding/matching an escrow receipt.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EvmError {
+    /// The receipt's `transactionHash` does not match the job's `escrow_hash`.
+    UnexpectedTransaction { expected: String, found: String },
+    /// The receipt `status` was not `0x1`.
+    FailedTransaction,
+    /// No matching `Transfer` to the expected recipient was found.
+    NoMatchingTransfer,
+    /// A hex field was malformed.
+    MalformedHex(String),
+    /// The transferred value is smaller than the required amount.
+    InsufficientAmount { found: String, required: String },
+    /// A uint256 word did not fit in the supported range.
+    ValueOverflow,
+    /// The ABI layer failed to decode the log.
+    Abi(String),
+}
+
+impl core::fmt::Display for EvmError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            EvmError::UnexpectedTransaction { expected, found } => write!(
+                f,
+                "receipt is for transaction {} but the job's escrow_hash is {}",
+                found, expected
+            ),
+            EvmError::FailedTransaction => write!(f, "transaction receipt status is not success"),
+            EvmError::NoMatchingTransfer => {
+                write!(f, "no ERC-20 Transfer to the expected recipient was found")
+            }
+            EvmError::MalformedHex(s) => write!(f, "malformed hex field: {}", s),
+            EvmError::InsufficientAmount { found, required } => write!(
+                f,
+                "transferred {} wei is below the required {} wei",
+                found, required
+            ),
+            EvmError::ValueOverflow => write!(f, "uint256 value exceeds supported range"),
+            EvmError::Abi(s) => write!(f, "ABI decode error: {}", s),
+        }
+    }
+}
+
+/// Canonical signature of the ERC-20 transfer event.
+pub const TRANSFER_EVENT_SIGNATURE: &str = "Transfer(address,address,uint256)";
+
+/// `keccak256("Transfer(address,address,uint256)")` as a `0x` topic string.
+pub fn transfer_topic0() -> String {
+    to_hex(&keccak256(TRANSFER_EVENT_SIGNATURE.as_bytes()))
+}
+
+/// Decode a log as an ERC-20 `Transfer` event, or `None` if it is not one.
+///
+/// Decoding is delegated to the typed ABI layer ([`crate::abi`]) so the same
+/// event definition drives both escrow verification and any future deposit
+/// lookups, rather than duplicating `0x`-string handling.
+pub fn decode_transfer_log(log: &EthLog) -> Result<Option<DecodedTransfer>, EvmError> {
+    let contract =
+        crate::abi::Contract::load(crate::abi::ERC20_ABI).map_err(|e| EvmError::Abi(e.to_string()))?;
+    let tokens = match contract.decode_event("Transfer", &log.topics, &log.data) {
+        Ok(tokens) => tokens,
+        // A non-Transfer log is not an error - it simply isn't a match.
+        Err(crate::abi::AbiError::TopicMismatch) => return Ok(None),
+        Err(e) => return Err(EvmError::Abi(e.to_string())),
+    };
+
+    match tokens.as_slice() {
+        [crate::abi::Token::Address(from), crate::abi::Token::Address(to), crate::abi::Token::Uint256(value_wei)] => {
+            Ok(Some(DecodedTransfer {
+                from: from.clone(),
+                to: to.clone(),
+                value_wei: value_wei.clone(),
+            }))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Verify that `receipt` is for `job_tx_hash` and contains a successful
+/// ERC-20 `Transfer` to `expected_to` of at least `required_wei`, returning
+/// the decoded amount.
+pub fn verify_escrow_receipt(
+    receipt: &EthReceipt,
+    job_tx_hash: &str,
+    expected_to: &str,
+    required_wei: u128,
+) -> Result<String, EvmError> {
+    if normalize_hex(&receipt.transaction_hash) != normalize_hex(job_tx_hash) {
+        return Err(EvmError::UnexpectedTransaction {
+            expected: job_tx_hash.to_string(),
+            found: receipt.transaction_hash.clone(),
+        });
+    }
+
+    if !status_is_success(&receipt.status) {
+        return Err(EvmError::FailedTransaction);
+    }
+
+    let expected = normalize_address(expected_to);
+    for log in &receipt.logs {
+        if let Some(transfer) = decode_transfer_log(log)? {
+            if transfer.to == expected {
+                let value: u128 = transfer
+                    .value_wei
+                    .parse()
+                    .map_err(|_| EvmError::ValueOverflow)?;
+                if value < required_wei {
+                    return Err(EvmError::InsufficientAmount {
+                        found: transfer.value_wei,
+                        required: required_wei.to_string(),
+                    });
+                }
+                return Ok(transfer.value_wei);
+            }
+        }
+    }
+    Err(EvmError::NoMatchingTransfer)
+}
+
+// ----------------------------------------------------------------------------
+// Hex / word helpers
+// ----------------------------------------------------------------------------
+
+fn status_is_success(status: &str) -> bool {
+    let s = status.trim_start_matches("0x");
+    u64::from_str_radix(s, 16).map(|v| v == 1).unwrap_or(false)
+}
+
+fn normalize_hex(s: &str) -> String {
+    format!("0x{}", s.trim_start_matches("0x").to_lowercase())
+}
+
+fn normalize_address(addr: &str) -> String {
+    normalize_hex(addr)
+}
+
+pub(crate) fn from_hex(s: &str) -> Result<Vec<u8>, EvmError> {
+    let s = s.trim_start_matches("0x");
+    if s.len() % 2 != 0 {
+        return Err(EvmError::MalformedHex(s.to_string()));
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).map_err(|_| EvmError::MalformedHex(s.to_string())))
+        .collect()
+}
+
+pub(crate) fn to_hex(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(2 + bytes.len() * 2);
+    out.push_str("0x");
+    for b in bytes {
+        out.push_str(&format!("{:02x}", b));
+    }
+    out
+}
+
+// ----------------------------------------------------------------------------
+// Keccak-256 (Ethereum variant, 0x01 padding) - pure and WASM-deterministic.
+// ----------------------------------------------------------------------------
+
+/// Compute the Keccak-256 digest of `data`.
+pub fn keccak256(data: &[u8]) -> [u8; 32] {
+    const RATE: usize = 136; // 1088-bit rate for 256-bit output
+    let mut state = [0u64; 25];
+    let len = data.len();
+    let mut offset = 0;
+
+    while offset + RATE <= len {
+        absorb_block(&mut state, &data[offset..offset + RATE]);
+        keccak_f(&mut state);
+        offset += RATE;
+    }
+
+    let mut block = [0u8; RATE];
+    let rem = len - offset;
+    block[..rem].copy_from_slice(&data[offset..]);
+    block[rem] = 0x01;
+    block[RATE - 1] |= 0x80;
+    absorb_block(&mut state, &block);
+    keccak_f(&mut state);
+
+    let mut out = [0u8; 32];
+    for i in 0..4 {
+        out[i * 8..i * 8 + 8].copy_from_slice(&state[i].to_le_bytes());
+    }
+    out
+}
+
+fn absorb_block(state: &mut [u64; 25], block: &[u8]) {
+    for (i, chunk) in block.chunks_exact(8).enumerate() {
+        let mut word = [0u8; 8];
+        word.copy_from_slice(chunk);
+        state[i] ^= u64::from_le_bytes(word);
+    }
+}
+
+fn keccak_f(st: &mut [u64; 25]) {
+    const RNDC: [u64; 24] = [
+        0x0000000000000001,
+        0x0000000000008082,
+        0x800000000000808a,
+        0x8000000080008000,
+        0x000000000000808b,
+        0x0000000080000001,
+        0x8000000080008081,
+        0x8000000000008009,
+        0x000000000000008a,
+        0x0000000000000088,
+        0x0000000080008009,
+        0x000000008000000a,
+        0x000000008000808b,
+        0x800000000000008b,
+        0x8000000000008089,
+        0x8000000000008003,
+        0x8000000000008002,
+        0x8000000000000080,
+        0x000000000000800a,
+        0x800000008000000a,
+        0x8000000080008081,
+        0x8000000000008080,
+        0x0000000080000001,
+        0x8000000080008008,
+    ];
+    const ROTC: [u32; 24] = [
+        1, 3, 6, 10, 15, 21, 28, 36, 45, 55, 2, 14, 27, 41, 56, 8, 25, 43, 62, 18, 39, 61, 20, 44,
+    ];
+    const PILN: [usize; 24] = [
+        10, 7, 11, 17, 18, 3, 5, 16, 8, 21, 24, 4, 15, 23, 19, 13, 12, 2, 20, 14, 22, 9, 6, 1,
+    ];
+
+    let mut bc = [0u64; 5];
+    for round in 0..24 {
+        // Theta
+        for i in 0..5 {
+            bc[i] = st[i] ^ st[i + 5] ^ st[i + 10] ^ st[i + 15] ^ st[i + 20];
+        }
+        for i in 0..5 {
+            let t = bc[(i + 4) % 5] ^ bc[(i + 1) % 5].rotate_left(1);
+            for j in (0..25).step_by(5) {
+                st[j + i] ^= t;
+            }
+        }
+        // Rho + Pi
+        let mut t = st[1];
+        for i in 0..24 {
+            let j = PILN[i];
+            let tmp = st[j];
+            st[j] = t.rotate_left(ROTC[i]);
+            t = tmp;
+        }
+        // Chi
+        for j in (0..25).step_by(5) {
+            for i in 0..5 {
+                bc[i] = st[j + i];
+            }
+            for i in 0..5 {
+                st[j + i] ^= (!bc[(i + 1) % 5]) & bc[(i + 2) % 5];
+            }
+        }
+        // Iota
+        st[0] ^= RNDC[round];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keccak256_matches_known_empty_digest() {
+        // Well-known keccak256("") - used throughout the Ethereum ecosystem
+        // (e.g. as `EmptyCodeHash` in go-ethereum) so it's independently
+        // verifiable without trusting this implementation.
+        assert_eq!(
+            to_hex(&keccak256(b"")),
+            "0xc5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"
+        );
+    }
+
+    #[test]
+    fn transfer_topic0_matches_canonical_erc20_signature_hash() {
+        // The canonical `Transfer(address,address,uint256)` topic hash,
+        // identical across every ERC-20 deployment.
+        assert_eq!(
+            transfer_topic0(),
+            "0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef"
+        );
+    }
+
+    fn receipt_with_transfer(tx_hash: &str, status: &str, to: &str, value_wei: u128) -> EthReceipt {
+        let contract = crate::abi::Contract::load(crate::abi::ERC20_ABI).unwrap();
+        let topic0 = to_hex(&contract.event_topic0("Transfer").unwrap());
+
+        let mut from_word = [0u8; 32];
+        from_word[12..].copy_from_slice(&from_hex(&format!("0x{}", "11".repeat(20))).unwrap());
+        let mut to_word = [0u8; 32];
+        to_word[12..].copy_from_slice(&from_hex(to).unwrap());
+        let mut value_word = [0u8; 32];
+        value_word[16..].copy_from_slice(&value_wei.to_be_bytes());
+
+        EthReceipt {
+            transaction_hash: tx_hash.to_string(),
+            status: status.to_string(),
+            logs: vec![EthLog {
+                address: format!("0x{}", "aa".repeat(20)),
+                topics: vec![topic0, to_hex(&from_word), to_hex(&to_word)],
+                data: to_hex(&value_word),
+            }],
+        }
+    }
+
+    #[test]
+    fn verify_escrow_receipt_accepts_matching_transfer() {
+        let tx_hash = format!("0x{}", "33".repeat(32));
+        let expected_to = format!("0x{}", "22".repeat(20));
+        let receipt = receipt_with_transfer(&tx_hash, "0x1", &expected_to, 100);
+
+        let verified = verify_escrow_receipt(&receipt, &tx_hash, &expected_to, 50).unwrap();
+        assert_eq!(verified, "100");
+    }
+
+    #[test]
+    fn verify_escrow_receipt_rejects_receipt_for_another_transaction() {
+        let tx_hash = format!("0x{}", "33".repeat(32));
+        let other_hash = format!("0x{}", "44".repeat(32));
+        let expected_to = format!("0x{}", "22".repeat(20));
+        let receipt = receipt_with_transfer(&tx_hash, "0x1", &expected_to, 100);
+
+        let err = verify_escrow_receipt(&receipt, &other_hash, &expected_to, 50).unwrap_err();
+        assert!(matches!(err, EvmError::UnexpectedTransaction { .. }));
+    }
+
+    #[test]
+    fn verify_escrow_receipt_rejects_failed_transaction() {
+        let tx_hash = format!("0x{}", "33".repeat(32));
+        let expected_to = format!("0x{}", "22".repeat(20));
+        let receipt = receipt_with_transfer(&tx_hash, "0x0", &expected_to, 100);
+
+        let err = verify_escrow_receipt(&receipt, &tx_hash, &expected_to, 50).unwrap_err();
+        assert_eq!(err, EvmError::FailedTransaction);
+    }
+
+    #[test]
+    fn verify_escrow_receipt_rejects_insufficient_amount() {
+        let tx_hash = format!("0x{}", "33".repeat(32));
+        let expected_to = format!("0x{}", "22".repeat(20));
+        let receipt = receipt_with_transfer(&tx_hash, "0x1", &expected_to, 10);
+
+        let err = verify_escrow_receipt(&receipt, &tx_hash, &expected_to, 50).unwrap_err();
+        assert!(matches!(err, EvmError::InsufficientAmount { .. }));
+    }
+}
@@ -1,5 +1,8 @@
 use hdi::prelude::*;
 
+pub mod abi;
+pub mod evm;
+
 /// ACP Agent entry - represents an agent registered in the DHT
 #[hdk_entry_helper]
 #[derive(Clone, PartialEq)]
@@ -38,6 +41,9 @@ pub struct AcpJob {
     pub current_phase: String,
     /// Deliverable (if completed)
     pub deliverable: Option<String>,
+    /// On-chain escrow amount in wei, decoded and verified from the deposit
+    /// transaction receipt. `None` until `verify_escrow` confirms the transfer.
+    pub verified_amount_wei: Option<String>,
 }
 
 #[hdk_entry_defs]
@@ -52,6 +58,9 @@ pub enum LinkTypes {
     AgentToProfile,
     AllAgents,
     AgentToJobs,
+    /// Links a rotated (new) agent profile back to the profile it supersedes,
+    /// forming an auditable session-key history chain.
+    AgentKeyHistory,
 }
 
 /// Validate AcpAgent entry
@@ -116,6 +125,85 @@ pub fn validate_acp_job(job: AcpJob) -> ExternResult<ValidateCallbackResult> {
     Ok(ValidateCallbackResult::Valid)
 }
 
+/// Canonical forward ordering of job phases.
+///
+/// A job advances one step at a time along this path; `rejected` is reachable
+/// from any non-terminal phase, and `completed`/`rejected` are terminal.
+const PHASE_ORDER: [&str; 4] = ["requested", "negotiation", "transaction", "completed"];
+
+fn phase_index(phase: &str) -> Option<usize> {
+    PHASE_ORDER.iter().position(|p| *p == phase)
+}
+
+fn is_terminal(phase: &str) -> bool {
+    phase == "completed" || phase == "rejected"
+}
+
+/// Validate a phase transition recorded by a CRUD update on an `AcpJob`.
+pub fn validate_phase_transition(from: &str, to: &str) -> ValidateCallbackResult {
+    // A metadata-only update (e.g. recording the verified escrow amount) keeps
+    // the phase unchanged.
+    if from == to {
+        return ValidateCallbackResult::Valid;
+    }
+
+    if is_terminal(from) {
+        return ValidateCallbackResult::Invalid(format!(
+            "cannot transition out of terminal phase '{}'",
+            from
+        ));
+    }
+
+    // A job may be rejected from any non-terminal phase.
+    if to == "rejected" {
+        return ValidateCallbackResult::Valid;
+    }
+
+    match (phase_index(from), phase_index(to)) {
+        (Some(from_idx), Some(to_idx)) if to_idx == from_idx + 1 => ValidateCallbackResult::Valid,
+        (Some(_), Some(_)) => ValidateCallbackResult::Invalid(format!(
+            "illegal phase transition '{}' -> '{}'; phases advance one step at a time",
+            from, to
+        )),
+        _ => ValidateCallbackResult::Invalid(format!(
+            "unknown phase in transition '{}' -> '{}'",
+            from, to
+        )),
+    }
+}
+
+/// Validate an update to an `AcpJob`, enforcing legal phase transitions.
+pub fn validate_update_acp_job(
+    original: AcpJob,
+    updated: AcpJob,
+) -> ExternResult<ValidateCallbackResult> {
+    // The updated entry must still be a well-formed job.
+    if let ValidateCallbackResult::Invalid(reason) = validate_acp_job(updated.clone())? {
+        return Ok(ValidateCallbackResult::Invalid(reason));
+    }
+    if let ValidateCallbackResult::Invalid(reason) =
+        validate_phase_transition(&original.current_phase, &updated.current_phase)
+    {
+        return Ok(ValidateCallbackResult::Invalid(reason));
+    }
+
+    // A job may only advance from `requested` to `negotiation` once the
+    // original entry carries a `verified_amount_wei` (as recorded by
+    // `verify_escrow`). Only the field's presence is checked here; the receipt
+    // is not re-decoded. Rejection from `requested` needs no verified amount.
+    if original.current_phase == "requested"
+        && updated.current_phase == "negotiation"
+        && original.verified_amount_wei.is_none()
+    {
+        return Ok(ValidateCallbackResult::Invalid(
+            "cannot advance past 'requested' until escrow is verified (verified_amount_wei is unset)"
+                .to_string(),
+        ));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
 #[hdk_extern]
 pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
     match op {
@@ -135,6 +223,20 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                 None => Ok(ValidateCallbackResult::Valid),
             }
         }
+        Op::RegisterUpdate(update) => {
+            // Only phase transitions on AcpJob updates are constrained.
+            let (new_entry, original_entry) = match (&update.new_entry, &update.original_entry) {
+                (Some(new_entry), Some(original_entry)) => (new_entry, original_entry),
+                _ => return Ok(ValidateCallbackResult::Valid),
+            };
+            match (
+                AcpJob::try_from(new_entry),
+                AcpJob::try_from(original_entry),
+            ) {
+                (Ok(updated), Ok(original)) => validate_update_acp_job(original, updated),
+                _ => Ok(ValidateCallbackResult::Valid),
+            }
+        }
         Op::RegisterCreateLink(_create_link) => {
             // Validate link creation - all link types allowed for now
             Ok(ValidateCallbackResult::Valid)